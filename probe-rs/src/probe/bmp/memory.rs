@@ -0,0 +1,128 @@
+//! [`ArmMemoryInterface`] implementation for the Black Magic Probe.
+//!
+//! Memory access goes through the standard ARM debug architecture MEM-AP:
+//! the target address is written to `TAR`, and `DRW` reads/writes then
+//! transfer the word at that address (auto-incrementing per `CSW`), all via
+//! the same [`super::remote`] AP register commands used for DP/AP access
+//! elsewhere in this module.
+
+use crate::architecture::arm::memory::ArmMemoryInterface;
+use crate::architecture::arm::{ApAddress, ArmError};
+
+use super::BmpArmInterface;
+
+/// MEM-AP Control/Status Word.
+const REG_CSW: u8 = 0x00;
+/// MEM-AP Transfer Address Register.
+const REG_TAR: u8 = 0x04;
+/// MEM-AP Data Read/Write register.
+const REG_DRW: u8 = 0x0c;
+
+/// `CSW` value selecting 32-bit transfers with auto-increment of `TAR`
+/// after each `DRW` access, matching the MEM-AP field layout from the ARM
+/// Debug Interface Architecture Specification.
+const CSW_32BIT_AUTO_INCREMENT: u32 = 0x2200_0012;
+/// Same as [`CSW_32BIT_AUTO_INCREMENT`], but selecting 8-bit transfers.
+const CSW_8BIT_AUTO_INCREMENT: u32 = 0x2200_0010;
+
+/// A MEM-AP backed memory interface, borrowing the [`BmpArmInterface`] it
+/// was handed out of so register accesses keep going through the same
+/// remote-protocol link.
+pub(crate) struct BmpMemoryInterface<'probe> {
+    interface: &'probe mut BmpArmInterface,
+    ap: ApAddress,
+}
+
+impl<'probe> BmpMemoryInterface<'probe> {
+    pub(crate) fn new(interface: &'probe mut BmpArmInterface, ap: ApAddress) -> Self {
+        Self { interface, ap }
+    }
+
+    fn set_csw(&mut self, csw: u32) -> Result<(), ArmError> {
+        self.interface.write_raw_ap_register(self.ap, REG_CSW, csw)
+    }
+
+    fn set_tar(&mut self, address: u64) -> Result<(), ArmError> {
+        // The Black Magic Probe's remote protocol only addresses 32-bit
+        // targets; there is no large physical address extension command.
+        self.interface
+            .write_raw_ap_register(self.ap, REG_TAR, address as u32)
+    }
+}
+
+impl ArmMemoryInterface for BmpMemoryInterface<'_> {
+    fn read_word_32(&mut self, address: u64) -> Result<u32, ArmError> {
+        self.set_csw(CSW_32BIT_AUTO_INCREMENT)?;
+        self.set_tar(address)?;
+        self.interface.read_raw_ap_register(self.ap, REG_DRW)
+    }
+
+    fn read_word_8(&mut self, address: u64) -> Result<u8, ArmError> {
+        self.set_csw(CSW_8BIT_AUTO_INCREMENT)?;
+        self.set_tar(address)?;
+        // A byte lane access still transfers a 32-bit word over DRW; which
+        // byte of it holds the requested data is selected by the low bits
+        // of TAR, mirroring the ARM MEM-AP byte-lane convention.
+        let word = self.interface.read_raw_ap_register(self.ap, REG_DRW)?;
+        let shift = (address as u32 & 0x3) * 8;
+        Ok((word >> shift) as u8)
+    }
+
+    fn read_32(&mut self, address: u64, data: &mut [u32]) -> Result<(), ArmError> {
+        self.set_csw(CSW_32BIT_AUTO_INCREMENT)?;
+        self.set_tar(address)?;
+        for word in data.iter_mut() {
+            *word = self.interface.read_raw_ap_register(self.ap, REG_DRW)?;
+        }
+        Ok(())
+    }
+
+    fn read_8(&mut self, address: u64, data: &mut [u8]) -> Result<(), ArmError> {
+        for (i, byte) in data.iter_mut().enumerate() {
+            *byte = self.read_word_8(address + i as u64)?;
+        }
+        Ok(())
+    }
+
+    fn write_word_32(&mut self, address: u64, data: u32) -> Result<(), ArmError> {
+        self.set_csw(CSW_32BIT_AUTO_INCREMENT)?;
+        self.set_tar(address)?;
+        self.interface.write_raw_ap_register(self.ap, REG_DRW, data)
+    }
+
+    fn write_word_8(&mut self, address: u64, data: u8) -> Result<(), ArmError> {
+        self.set_csw(CSW_8BIT_AUTO_INCREMENT)?;
+        self.set_tar(address)?;
+        let shift = (address as u32 & 0x3) * 8;
+        self.interface
+            .write_raw_ap_register(self.ap, REG_DRW, (data as u32) << shift)
+    }
+
+    fn write_32(&mut self, address: u64, data: &[u32]) -> Result<(), ArmError> {
+        self.set_csw(CSW_32BIT_AUTO_INCREMENT)?;
+        self.set_tar(address)?;
+        for &word in data {
+            self.interface.write_raw_ap_register(self.ap, REG_DRW, word)?;
+        }
+        Ok(())
+    }
+
+    fn write_8(&mut self, address: u64, data: &[u8]) -> Result<(), ArmError> {
+        for (i, &byte) in data.iter().enumerate() {
+            self.write_word_8(address + i as u64, byte)?;
+        }
+        Ok(())
+    }
+
+    fn supports_native_64bit_access(&mut self) -> bool {
+        // The remote protocol's AP register commands only ever move a
+        // single 32-bit word per round trip.
+        false
+    }
+
+    fn flush(&mut self) -> Result<(), ArmError> {
+        // Every access above is already a synchronous remote-protocol
+        // round trip, so there is nothing batched to flush.
+        Ok(())
+    }
+}
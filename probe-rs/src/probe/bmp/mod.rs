@@ -4,7 +4,49 @@ use crate::{
 };
 use rusb::{Device, UsbContext};
 use serialport::{available_ports, SerialPort, SerialPortType};
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+mod arm;
+mod memory;
+mod remote;
+mod reset;
+mod rsp;
+mod uart;
+
+pub use arm::BmpArmInterface;
+pub use uart::BmpUart;
+
+/// Error conditions specific to talking to a Black Magic Probe that don't
+/// already have a matching [`DebugProbeError`] variant.
+#[derive(Debug)]
+pub(crate) enum BmpError {
+    Io(std::io::Error),
+    Serial(serialport::Error),
+    Protocol(String),
+    /// A probe-rs capability BMP's remote protocol has no command for.
+    Unsupported(&'static str),
+}
+
+impl std::fmt::Display for BmpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BmpError::Io(e) => write!(f, "I/O error communicating with probe: {e}"),
+            BmpError::Serial(e) => write!(f, "error controlling serial port: {e}"),
+            BmpError::Protocol(msg) => write!(f, "BMP protocol error: {msg}"),
+            BmpError::Unsupported(what) => {
+                write!(f, "{what} is not supported over BMP's remote protocol")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BmpError {}
+
+impl BmpError {
+    fn probe_specific(self) -> DebugProbeError {
+        DebugProbeError::ProbeSpecific(Box::new(self))
+    }
+}
 
 pub struct BMPProbe {
     pub device: BMPDevice,
@@ -29,16 +71,65 @@ impl BMPProbe {
             speed: 1000,
         }
     }
+
+    /// Like [`DebugProbe::new_from_selector`], but keeps rescanning for the
+    /// probe's serial port for up to `timeout` instead of giving up on the
+    /// first miss. Useful right after the probe re-enumerates following a
+    /// reset or DFU exit, where the port can take a moment to appear.
+    pub fn new_from_selector_with_timeout(
+        selector: impl Into<DebugProbeSelector>,
+        timeout: Duration,
+    ) -> Result<Box<Self>, DebugProbeError> {
+        Ok(Box::new(BMPProbe::new_from_device(
+            open_device_from_selector_with_timeout(selector, timeout)?,
+        )))
+    }
 }
 pub struct BMPDevice {
-    _port: Box<dyn SerialPort>,
+    port: Box<dyn SerialPort>,
+    port_name: String,
+    serial_number: Option<String>,
+    /// Whether the remote protocol's `!HC`/`!GA` handshake has already run.
+    /// See [`BMPDevice::remote_command`].
+    remote_initialized: bool,
 }
 
+/// How often [`open_device_from_selector_with_timeout`] rescans
+/// `available_ports()` while waiting for the probe's serial port to appear.
+const PORT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Read timeout for the GDB serial port. Generous enough to cover a slow
+/// RSP round-trip (e.g. a target reset or flash-adjacent AP access)
+/// without blocking forever on a probe that has gone away.
+const GDB_PORT_READ_TIMEOUT: Duration = Duration::from_secs(2);
+
 fn open_device_from_selector(
     selector: impl Into<DebugProbeSelector>,
+) -> Result<BMPDevice, ProbeCreationError> {
+    open_device_from_selector_with_timeout(selector, Duration::ZERO)
+}
+
+/// Scans for the serial port matching `selector`, retrying every
+/// [`PORT_POLL_INTERVAL`] until it appears or `timeout` elapses.
+fn open_device_from_selector_with_timeout(
+    selector: impl Into<DebugProbeSelector>,
+    timeout: Duration,
 ) -> Result<BMPDevice, ProbeCreationError> {
     let selector = selector.into();
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        match try_open_device_from_selector(&selector) {
+            Ok(device) => return Ok(device),
+            Err(e) if Instant::now() >= deadline => return Err(e),
+            Err(_) => std::thread::sleep(PORT_POLL_INTERVAL),
+        }
+    }
+}
 
+fn try_open_device_from_selector(
+    selector: &DebugProbeSelector,
+) -> Result<BMPDevice, ProbeCreationError> {
     match available_ports() {
         Ok(ports) => {
             for p in ports {
@@ -47,14 +138,19 @@ fn open_device_from_selector(
                     SerialPortType::UsbPort(info) => {
                         if (info.vid == selector.vendor_id) & (info.pid == selector.product_id) {
                             log::debug!("Found matching serial port: {}", p.port_name);
-                            let port = serialport::new(p.port_name, 115_200)
-                                .timeout(Duration::from_millis(10))
+                            let port = serialport::new(p.port_name.clone(), 115_200)
+                                .timeout(GDB_PORT_READ_TIMEOUT)
                                 .open();
 
                             match port {
                                 Ok(port) => {
                                     log::debug!("Serial port opened successfuly");
-                                    return Ok(BMPDevice { _port: port });
+                                    return Ok(BMPDevice {
+                                        port,
+                                        port_name: p.port_name,
+                                        serial_number: info.serial_number,
+                                        remote_initialized: false,
+                                    });
                                 }
                                 Err(_e) => {
                                     return Err(ProbeCreationError::NotFound);
@@ -92,21 +188,28 @@ impl DebugProbe for BMPProbe {
     }
 
     fn set_speed(&mut self, speed_khz: u32) -> Result<u32, DebugProbeError> {
-        self.speed = speed_khz;
+        let actual_speed_khz = self.device.remote_set_frequency(speed_khz)?;
+        self.speed = actual_speed_khz;
 
-        Ok(speed_khz)
+        Ok(actual_speed_khz)
     }
 
     fn attach(&mut self) -> Result<(), DebugProbeError> {
         log::debug!("Attaching with protocol '{}'", self.protocol);
+        self.device.remote_select_protocol(self.protocol)?;
+
+        let targets = self.device.remote_scan()?;
+        log::debug!("Bus scan found {targets} target(s)");
+
         Ok(())
     }
 
     fn select_protocol(&mut self, protocol: WireProtocol) -> Result<(), DebugProbeError> {
-        if protocol != WireProtocol::Jtag {
-            Err(DebugProbeError::UnsupportedProtocol(protocol))
-        } else {
-            Ok(())
+        match protocol {
+            WireProtocol::Swd | WireProtocol::Jtag => {
+                self.protocol = protocol;
+                Ok(())
+            }
         }
     }
 
@@ -115,18 +218,27 @@ impl DebugProbe for BMPProbe {
     }
 
     fn target_reset(&mut self) -> Result<(), DebugProbeError> {
-        log::error!("BMP target_reset");
-        unimplemented!()
+        self.device.rsp_reset()
     }
 
     fn target_reset_assert(&mut self) -> Result<(), DebugProbeError> {
-        log::error!("BMP target_assert");
-        unimplemented!()
+        match self.device.remote_set_reset_pin(true) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                log::debug!("Remote nRST control failed ({e}), falling back to DTR");
+                self.device.toggle_reset_via_serial_lines(true)
+            }
+        }
     }
 
     fn target_reset_deassert(&mut self) -> Result<(), DebugProbeError> {
-        log::error!("BMP target_reset_deassert");
-        unimplemented!()
+        match self.device.remote_set_reset_pin(false) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                log::debug!("Remote nRST control failed ({e}), falling back to DTR");
+                self.device.toggle_reset_via_serial_lines(false)
+            }
+        }
     }
 
     fn into_probe(self: Box<Self>) -> Box<dyn DebugProbe> {
@@ -143,7 +255,7 @@ impl DebugProbe for BMPProbe {
         Box<dyn crate::architecture::arm::communication_interface::ArmProbeInterface + 'probe>,
         (Box<dyn DebugProbe>, DebugProbeError),
     > {
-        todo!()
+        Ok(Box::new(BmpArmInterface::new(self)))
     }
 }
 
@@ -0,0 +1,179 @@
+//! The Black Magic Probe's binary "remote" protocol.
+//!
+//! On top of the RSP framing implemented in [`super::rsp`], BMP firmware
+//! understands a second, binary protocol carried inside RSP packets whose
+//! payload starts with `!`. It is how probe-rs drives the physical SWD/JTAG
+//! pins and DP/AP registers without going through GDB's own target
+//! description machinery.
+//!
+//! Every remote command is ASCII and echoes back either `K` followed by a
+//! hex payload on success, or `E` followed by an error code on failure.
+
+use crate::architecture::arm::{ApAddress, DpAddress};
+use crate::probe::{DebugProbeError, WireProtocol};
+
+use super::{BMPDevice, BmpError};
+
+/// High voltage / protocol negotiation, equivalent to BMP's `!HC` request.
+const CMD_HIGH_LEVEL_INIT: &str = "HC";
+/// Generic accelerated (`!GA`) init, used once a protocol has been picked.
+const CMD_GENERAL_INIT: &str = "GA";
+const CMD_SWD_INIT: &str = "Sa";
+const CMD_JTAG_INIT: &str = "Ja";
+const CMD_JTAG_TO_SWD: &str = "Ja2";
+const CMD_SCAN: &str = "s";
+const CMD_DP_READ: &str = "Ad";
+const CMD_DP_WRITE: &str = "Ae";
+const CMD_AP_READ: &str = "Ap";
+const CMD_AP_WRITE: &str = "Aq";
+const CMD_SET_FREQUENCY: &str = "F";
+
+impl BMPDevice {
+    /// Sends `!<cmd>` to the probe and returns the hex payload of a `K`
+    /// (ok) response, or a [`DebugProbeError`] built from an `E<code>`
+    /// response. Does not touch [`BMPDevice::remote_initialized`]; callers
+    /// go through [`Self::remote_command`] unless they're part of the
+    /// initialization handshake itself.
+    fn raw_remote_command(&mut self, cmd: &str) -> Result<String, DebugProbeError> {
+        let mut payload = Vec::with_capacity(cmd.len() + 1);
+        payload.push(b'!');
+        payload.extend_from_slice(cmd.as_bytes());
+
+        self.send_packet(&payload)?;
+        let response = self.recv_packet()?;
+
+        match response.split_first() {
+            Some((b'K', rest)) => Ok(String::from_utf8_lossy(rest).into_owned()),
+            Some((b'E', rest)) => Err(BmpError::Protocol(format!(
+                "probe reported error {}",
+                String::from_utf8_lossy(rest)
+            ))
+            .probe_specific()),
+            _ => Err(BmpError::Protocol("malformed remote protocol response".into())
+                .probe_specific()),
+        }
+    }
+
+    /// Runs the `!HC`/`!GA` protocol/voltage handshake the first time the
+    /// remote protocol is used. `attach()` normally triggers this via
+    /// [`Self::remote_select_protocol`], but callers like `set_speed` or
+    /// `target_reset*` can run before `attach` and must not talk to an
+    /// uninitialized remote link.
+    pub(super) fn ensure_remote_initialized(&mut self) -> Result<(), DebugProbeError> {
+        if self.remote_initialized {
+            return Ok(());
+        }
+
+        self.raw_remote_command(CMD_HIGH_LEVEL_INIT)?;
+        self.raw_remote_command(CMD_GENERAL_INIT)?;
+        self.remote_initialized = true;
+
+        Ok(())
+    }
+
+    /// Like [`Self::raw_remote_command`], but first makes sure the remote
+    /// protocol has been initialized.
+    pub(super) fn remote_command(&mut self, cmd: &str) -> Result<String, DebugProbeError> {
+        self.ensure_remote_initialized()?;
+        self.raw_remote_command(cmd)
+    }
+
+    /// Parses a `K`-prefixed hex payload as a `u32`, as returned by the
+    /// DP/AP register read commands.
+    fn remote_command_hex(&mut self, cmd: &str) -> Result<u32, DebugProbeError> {
+        let hex = self.remote_command(cmd)?;
+        u32::from_str_radix(&hex, 16)
+            .map_err(|_| BmpError::Protocol(format!("expected hex payload, got {hex:?}")).probe_specific())
+    }
+
+    /// Negotiates protocol/voltage with the probe (if not already done) and
+    /// switches the physical wire mode to `protocol`.
+    pub(crate) fn remote_select_protocol(
+        &mut self,
+        protocol: WireProtocol,
+    ) -> Result<(), DebugProbeError> {
+        match protocol {
+            WireProtocol::Swd => {
+                // The target first has to be coaxed out of JTAG mode, in
+                // case it was left there by a previous session, before the
+                // SWD-specific init (which performs the line reset) runs.
+                self.remote_command(CMD_JTAG_TO_SWD)?;
+                self.remote_command(CMD_SWD_INIT)?;
+            }
+            WireProtocol::Jtag => {
+                self.remote_command(CMD_JTAG_INIT)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Pushes the requested SWD/JTAG clock frequency to the probe and
+    /// returns the (possibly clamped) frequency it reports back.
+    pub(crate) fn remote_set_frequency(&mut self, frequency_khz: u32) -> Result<u32, DebugProbeError> {
+        let cmd = format!("{CMD_SET_FREQUENCY}{frequency_khz:08x}");
+        self.remote_command_hex(&cmd)
+    }
+
+    /// Scans the bus for attached targets. Returns the number the probe
+    /// reports finding; probe-rs doesn't need the enumerated details since
+    /// it re-discovers them through the DP/AP register interface.
+    pub(crate) fn remote_scan(&mut self) -> Result<u32, DebugProbeError> {
+        self.remote_command_hex(CMD_SCAN)
+    }
+
+    pub(crate) fn remote_read_dp_register(
+        &mut self,
+        dp: DpAddress,
+        address: u8,
+    ) -> Result<u32, DebugProbeError> {
+        let cmd = format!("{CMD_DP_READ}{:08x}{address:02x}", dp_index(dp));
+        self.remote_command_hex(&cmd)
+    }
+
+    pub(crate) fn remote_write_dp_register(
+        &mut self,
+        dp: DpAddress,
+        address: u8,
+        value: u32,
+    ) -> Result<(), DebugProbeError> {
+        let cmd = format!("{CMD_DP_WRITE}{:08x}{address:02x}{value:08x}", dp_index(dp));
+        self.remote_command(&cmd).map(|_| ())
+    }
+
+    pub(crate) fn remote_read_ap_register(
+        &mut self,
+        ap: ApAddress,
+        address: u8,
+    ) -> Result<u32, DebugProbeError> {
+        let cmd = format!(
+            "{CMD_AP_READ}{:08x}{:02x}{address:02x}",
+            dp_index(ap.dp),
+            ap.ap
+        );
+        self.remote_command_hex(&cmd)
+    }
+
+    pub(crate) fn remote_write_ap_register(
+        &mut self,
+        ap: ApAddress,
+        address: u8,
+        value: u32,
+    ) -> Result<(), DebugProbeError> {
+        let cmd = format!(
+            "{CMD_AP_WRITE}{:08x}{:02x}{address:02x}{value:08x}",
+            dp_index(ap.dp),
+            ap.ap
+        );
+        self.remote_command(&cmd).map(|_| ())
+    }
+}
+
+/// BMP's remote protocol addresses the debug port by its multidrop target
+/// selector, defaulting to `0` for the (non-multidrop) default DP.
+fn dp_index(dp: DpAddress) -> u32 {
+    match dp {
+        DpAddress::Default => 0,
+        DpAddress::Multidrop(target_sel) => target_sel,
+    }
+}
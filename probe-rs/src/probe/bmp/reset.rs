@@ -0,0 +1,65 @@
+//! Target reset handling for the Black Magic Probe.
+//!
+//! `target_reset` asks the probe to reset and resume the target via the RSP
+//! extended "restart" request. Asserting/deasserting nRST instead goes
+//! through the remote protocol's pin-control command, falling back to
+//! toggling the serial port's DTR line for boards wired to reset via a
+//! UART's handshaking lines instead of a dedicated nRST pin. This mirrors
+//! the espflash-style reset sequence, where DTR and RTS are driven to
+//! *opposite* levels (one line holds nRST, the other holds a boot-mode
+//! select pin like ESP32's IO0) rather than identically - so only the line
+//! mapped to nRST is touched here, leaving the other free for whatever the
+//! board wires it to.
+
+use std::time::Duration;
+
+use super::{BMPDevice, BmpError};
+use crate::DebugProbeError;
+
+/// Command used to drive the probe's nRST line through the remote
+/// protocol.
+const CMD_SET_RESET_PIN: &str = "Gz";
+
+/// Settle time between toggling DTR and continuing, mirroring the
+/// classic DTR/RTS reset sequence.
+const RESET_PULSE_DELAY: Duration = Duration::from_millis(100);
+
+impl BMPDevice {
+    /// Sends the RSP extended "restart" request, asking the probe to reset
+    /// and resume the target. `R` takes a (ignored) two-hex-digit argument
+    /// and is only valid once the remote link has been established, so this
+    /// makes sure that has happened before sending `R00`.
+    pub(crate) fn rsp_reset(&mut self) -> Result<(), DebugProbeError> {
+        self.ensure_remote_initialized()?;
+        self.send_packet(b"R00")
+    }
+
+    /// Drives the probe's nRST line through the remote protocol's
+    /// pin-control command.
+    pub(crate) fn remote_set_reset_pin(&mut self, asserted: bool) -> Result<(), DebugProbeError> {
+        let cmd = format!("{CMD_SET_RESET_PIN}{}", asserted as u8);
+        self.remote_command(&cmd).map(|_| ())
+    }
+
+    /// Falls back to toggling the serial port's DTR line directly, for
+    /// boards wired to reset via a UART's handshaking lines rather than a
+    /// dedicated nRST pin. `asserted` selects whether the target should end
+    /// up held in reset or released.
+    ///
+    /// Only DTR is driven; RTS is left alone since boards using this
+    /// fallback typically wire it to a separate boot-mode select pin (as in
+    /// espflash's reset sequence), and toggling it in lockstep with nRST
+    /// would force the target into that mode on every reset.
+    pub(crate) fn toggle_reset_via_serial_lines(
+        &mut self,
+        asserted: bool,
+    ) -> Result<(), DebugProbeError> {
+        self.port
+            .write_data_terminal_ready(asserted)
+            .map_err(|e| BmpError::Serial(e).probe_specific())?;
+
+        std::thread::sleep(RESET_PULSE_DELAY);
+
+        Ok(())
+    }
+}
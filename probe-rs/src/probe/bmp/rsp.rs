@@ -0,0 +1,204 @@
+//! GDB Remote Serial Protocol (RSP) packet framing over the Black Magic
+//! Probe's GDB serial port.
+//!
+//! Every packet is framed as `$<payload>#<checksum>`, where `<checksum>` is
+//! the two lowercase-hex digits of the 8-bit sum of the literal bytes sent
+//! on the wire between `$` and `#` (i.e. after escaping, before run-length
+//! expansion). The receiver acknowledges a well-formed packet with `+` and
+//! asks for a retransmit with `-`.
+
+use std::io::{Read, Write};
+
+use super::{BMPDevice, BmpError};
+use crate::DebugProbeError;
+
+const START_OF_PACKET: u8 = b'$';
+const END_OF_PACKET: u8 = b'#';
+const ESCAPE: u8 = b'}';
+const RUN_LENGTH: u8 = b'*';
+const ACK: u8 = b'+';
+const NACK: u8 = b'-';
+
+/// Number of times we send a packet, counting the first attempt, before
+/// giving up.
+const MAX_SEND_RETRIES: usize = 5;
+
+/// Number of times we ask for a retransmit after a checksum mismatch
+/// before giving up.
+const MAX_RECV_RETRIES: usize = 5;
+
+/// 8-bit sum of all bytes, as required for the RSP checksum.
+fn checksum(payload: &[u8]) -> u8 {
+    payload.iter().fold(0u8, |acc, byte| acc.wrapping_add(*byte))
+}
+
+/// Escapes `$`, `#`, `}` and `*` in `payload` using the RSP escape byte.
+fn escape(payload: &[u8]) -> Vec<u8> {
+    let mut escaped = Vec::with_capacity(payload.len());
+    for &byte in payload {
+        match byte {
+            START_OF_PACKET | END_OF_PACKET | ESCAPE | RUN_LENGTH => {
+                escaped.push(ESCAPE);
+                escaped.push(byte ^ 0x20);
+            }
+            _ => escaped.push(byte),
+        }
+    }
+    escaped
+}
+
+/// Reverses [`escape`] and expands the RSP run-length encoding, where `*`
+/// followed by a byte `n` means "repeat the previous byte `n - 29` times".
+fn unescape(raw: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(raw.len());
+    let mut i = 0;
+    while i < raw.len() {
+        match raw[i] {
+            ESCAPE => {
+                i += 1;
+                if let Some(&byte) = raw.get(i) {
+                    out.push(byte ^ 0x20);
+                }
+            }
+            RUN_LENGTH => {
+                i += 1;
+                if let (Some(&prev), Some(&count_byte)) = (out.last(), raw.get(i)) {
+                    let count = count_byte.saturating_sub(29) as usize;
+                    out.extend(std::iter::repeat(prev).take(count));
+                }
+            }
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    out
+}
+
+impl BMPDevice {
+    /// Frames `payload` as an RSP packet, writes it to the probe and waits
+    /// for the single-byte ack, resending on a `-` (nack) or any other
+    /// non-ack byte, for at most [`MAX_SEND_RETRIES`] sends in all.
+    pub fn send_packet(&mut self, payload: &[u8]) -> Result<(), DebugProbeError> {
+        let escaped = escape(payload);
+
+        let mut frame = Vec::with_capacity(escaped.len() + 4);
+        frame.push(START_OF_PACKET);
+        frame.extend_from_slice(&escaped);
+        frame.push(END_OF_PACKET);
+        frame.extend(format!("{:02x}", checksum(&escaped)).into_bytes());
+
+        for attempt in 1..=MAX_SEND_RETRIES {
+            self.write_all(&frame)?;
+
+            match self.read_byte()? {
+                ACK => return Ok(()),
+                NACK => {
+                    log::debug!("BMP nack'd packet, retrying ({attempt}/{MAX_SEND_RETRIES})");
+                }
+                other => {
+                    log::debug!("Unexpected ack byte {other:#x}, retrying");
+                }
+            }
+        }
+
+        Err(BmpError::Protocol("probe never acked packet".into()).probe_specific())
+    }
+
+    /// Reads the next RSP packet from the probe, verifies its checksum and
+    /// acks (or nacks) it accordingly, re-reading the retransmitted packet
+    /// after a nack, for at most [`MAX_RECV_RETRIES`] packets read in all.
+    pub fn recv_packet(&mut self) -> Result<Vec<u8>, DebugProbeError> {
+        for attempt in 1..=MAX_RECV_RETRIES {
+            loop {
+                if self.read_byte()? == START_OF_PACKET {
+                    break;
+                }
+            }
+
+            let mut raw = Vec::new();
+            loop {
+                let byte = self.read_byte()?;
+                if byte == END_OF_PACKET {
+                    break;
+                }
+                raw.push(byte);
+            }
+
+            let mut checksum_digits = [0u8; 2];
+            self.read_exact(&mut checksum_digits)?;
+            let expected = std::str::from_utf8(&checksum_digits)
+                .ok()
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok());
+
+            if expected == Some(checksum(&raw)) {
+                self.write_all(&[ACK])?;
+                return Ok(unescape(&raw));
+            }
+
+            log::debug!(
+                "BMP packet failed checksum, requesting retransmit ({attempt}/{MAX_RECV_RETRIES})"
+            );
+            self.write_all(&[NACK])?;
+        }
+
+        Err(BmpError::Protocol("exceeded retries waiting for a valid packet".into())
+            .probe_specific())
+    }
+
+    fn read_byte(&mut self) -> Result<u8, DebugProbeError> {
+        let mut byte = [0u8; 1];
+        self.read_exact(&mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), DebugProbeError> {
+        self.port
+            .read_exact(buf)
+            .map_err(|e| BmpError::Io(e).probe_specific())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), DebugProbeError> {
+        self.port
+            .write_all(buf)
+            .map_err(|e| BmpError::Io(e).probe_specific())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_then_unescape_round_trips_special_bytes() {
+        let payload = b"$#}*hello";
+        let escaped = escape(payload);
+        assert_eq!(unescape(&escaped), payload);
+    }
+
+    #[test]
+    fn run_length_expands_repeated_byte() {
+        // "0" followed by a run-length marker asking for 3 more repeats
+        // (b' ' - 29 == 3) should expand to four '0' bytes.
+        let raw = [b'0', RUN_LENGTH, b' '];
+        assert_eq!(unescape(&raw), b"0000");
+    }
+
+    #[test]
+    fn checksum_is_computed_over_escaped_wire_bytes() {
+        let payload = b"$AT#42";
+        let escaped = escape(payload);
+
+        // send_packet embeds checksum(&escaped) in the frame, not
+        // checksum(payload) - the two must differ once escaping changes
+        // the byte stream.
+        assert_ne!(checksum(payload), checksum(&escaped));
+
+        // `$` and `#` each expand to two escaped bytes (ESCAPE, byte ^
+        // 0x20), so "$AT#42" escapes to [0x7d, 0x04, b'A', b'T', 0x7d,
+        // 0x03, b'4', b'2'], which sums to 0xfc. Pinning this known-good
+        // value (rather than re-deriving it via escape()) is what catches
+        // a regression to checksumming the raw payload instead of the wire
+        // bytes.
+        assert_eq!(checksum(&escaped), 0xfc);
+    }
+}
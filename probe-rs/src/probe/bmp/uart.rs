@@ -0,0 +1,131 @@
+//! Access to the Black Magic Probe's secondary "target UART" passthrough.
+//!
+//! A BMP enumerates two CDC-ACM interfaces: the first is the GDB RSP port
+//! used everywhere else in this module, the second passes the target's UART
+//! straight through. This locates that second port — the other `port_name`
+//! sharing the GDB port's USB serial number — and pumps its bytes into a
+//! channel on a background thread, so the host app can show or forward
+//! target `println!` output alongside debugging.
+//!
+//! This is plain UART passthrough, not RTT: BMP's remote protocol has no
+//! RTT control-block command, so there's nothing here to wire into
+//! probe-rs's RTT plumbing (`crate::rtt`), which expects to read control
+//! blocks out of target memory via an `ArmMemoryInterface` instead of a
+//! serial port. Host-side visibility goes through the same `log` facade
+//! used elsewhere in this module: each line the target prints is also
+//! logged under the `probe_rs::probe::bmp::target_uart` log target
+//! ([`TARGET_UART_LOG_TARGET`]), so it shows up alongside the rest of probe-rs's
+//! diagnostics without a separate display mechanism.
+
+use std::io::Read;
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+use serialport::SerialPortType;
+
+use crate::DebugProbeError;
+
+use super::{BmpError, BMPDevice, BMPProbe};
+
+const TARGET_UART_BAUD_RATE: u32 = 115_200;
+const TARGET_UART_READ_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Log target for lines read from the target UART, so they can be told
+/// apart from the probe's own diagnostics and filtered independently.
+const TARGET_UART_LOG_TARGET: &str = "probe_rs::probe::bmp::target_uart";
+
+/// A live connection to the BMP's target UART passthrough, fed by a
+/// background reader thread.
+pub struct BmpUart {
+    rx: Receiver<Vec<u8>>,
+}
+
+impl BmpUart {
+    /// Drains all bytes the background reader thread has received so far,
+    /// without blocking.
+    pub fn read_available(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        while let Ok(chunk) = self.rx.try_recv() {
+            data.extend(chunk);
+        }
+        data
+    }
+}
+
+impl BMPProbe {
+    /// Opens the probe's secondary target-UART port and spawns a
+    /// background thread that streams its output into the returned
+    /// [`BmpUart`].
+    pub fn open_target_uart(&self) -> Result<BmpUart, DebugProbeError> {
+        let port_name = self.device.find_target_uart_port_name()?;
+
+        let mut port = serialport::new(port_name, TARGET_UART_BAUD_RATE)
+            .timeout(TARGET_UART_READ_TIMEOUT)
+            .open()
+            .map_err(|e| BmpError::Serial(e).probe_specific())?;
+
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 256];
+            let mut line = Vec::new();
+            loop {
+                match port.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        log_complete_lines(&mut line, &buf[..n]);
+                        if tx.send(buf[..n].to_vec()).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::TimedOut => continue,
+                    Err(e) => {
+                        log::debug!("BMP target UART reader thread exiting: {e}");
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(BmpUart { rx })
+    }
+}
+
+/// Appends `chunk` to the in-progress `line` buffer, logging (and clearing)
+/// it each time a `\n` completes one.
+fn log_complete_lines(line: &mut Vec<u8>, chunk: &[u8]) {
+    for &byte in chunk {
+        if byte == b'\n' {
+            log::info!(target: TARGET_UART_LOG_TARGET, "{}", String::from_utf8_lossy(line));
+            line.clear();
+        } else {
+            line.push(byte);
+        }
+    }
+}
+
+impl BMPDevice {
+    /// Finds the port belonging to the probe's second CDC-ACM interface.
+    fn find_target_uart_port_name(&self) -> Result<String, DebugProbeError> {
+        let serial_number = self.serial_number.as_deref().ok_or_else(|| {
+            BmpError::Protocol("probe has no USB serial number to match against".into())
+                .probe_specific()
+        })?;
+
+        let ports =
+            serialport::available_ports().map_err(|e| BmpError::Serial(e).probe_specific())?;
+
+        for p in ports {
+            let SerialPortType::UsbPort(info) = &p.port_type else {
+                continue;
+            };
+            if info.serial_number.as_deref() != Some(serial_number) {
+                continue;
+            }
+            if p.port_name != self.port_name {
+                return Ok(p.port_name);
+            }
+        }
+
+        Err(BmpError::Protocol("could not find BMP's target UART port".into()).probe_specific())
+    }
+}